@@ -1,10 +1,78 @@
 use crate::deflate::{State, HASH_SIZE, STD_MIN_MATCH};
+use core::sync::atomic::{AtomicU8, Ordering};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HashCalcVariant {
     Standard,
     Crc32,
     Roll,
+    Aes,
+}
+
+impl HashCalcVariant {
+    const UNINIT: u8 = 0;
+    const STANDARD: u8 = 1;
+    const CRC32: u8 = 2;
+    const AES: u8 = 3;
+
+    /// Picks the best `HashCalcVariant` for the current CPU and caches the
+    /// result, so that hot loops can call this cheaply instead of re-running
+    /// feature detection (e.g. `Crc32HashCalc::is_supported`) on every call.
+    ///
+    /// AES rounds give better avalanche than the CRC32/multiplicative hashes,
+    /// so `Aes` is preferred when available; `Crc32` is otherwise preferred
+    /// over `Standard` since it has dedicated hardware support.
+    ///
+    /// Mirrors the detect-once/cache approach of BLAKE3's `platform.rs`.
+    pub fn detect() -> Self {
+        static CACHE: AtomicU8 = AtomicU8::new(HashCalcVariant::UNINIT);
+
+        match CACHE.load(Ordering::Relaxed) {
+            Self::STANDARD => return HashCalcVariant::Standard,
+            Self::CRC32 => return HashCalcVariant::Crc32,
+            Self::AES => return HashCalcVariant::Aes,
+            _ => {}
+        }
+
+        let detected = if AesHashCalc::is_supported() {
+            HashCalcVariant::Aes
+        } else if Crc32HashCalc::is_supported() {
+            HashCalcVariant::Crc32
+        } else {
+            HashCalcVariant::Standard
+        };
+
+        let cached = match detected {
+            HashCalcVariant::Aes => Self::AES,
+            HashCalcVariant::Crc32 => Self::CRC32,
+            _ => Self::STANDARD,
+        };
+        CACHE.store(cached, Ordering::Relaxed);
+
+        detected
+    }
+
+    /// Enum-dispatch equivalent of [`HashCalc::update_hash`] for the detected
+    /// variant, so callers don't need to monomorphize on a concrete type.
+    pub fn update_hash(self, h: u32, val: u32) -> u32 {
+        match self {
+            HashCalcVariant::Standard => StandardHashCalc::update_hash(h, val),
+            HashCalcVariant::Crc32 => Crc32HashCalc::update_hash(h, val),
+            HashCalcVariant::Roll => RollHashCalc::update_hash(h, val),
+            HashCalcVariant::Aes => AesHashCalc::update_hash(h, val),
+        }
+    }
+
+    /// Enum-dispatch equivalent of [`HashCalc::insert_string`] for the
+    /// detected variant.
+    pub fn insert_string(self, state: &mut State, string: usize, count: usize) {
+        match self {
+            HashCalcVariant::Standard => StandardHashCalc::insert_string(state, string, count),
+            HashCalcVariant::Crc32 => Crc32HashCalc::insert_string(state, string, count),
+            HashCalcVariant::Roll => RollHashCalc::insert_string(state, string, count),
+            HashCalcVariant::Aes => AesHashCalc::insert_string(state, string, count),
+        }
+    }
 }
 
 pub trait HashCalc {
@@ -63,6 +131,131 @@ impl HashCalc for StandardHashCalc {
         const HASH_SLIDE: u32 = 16;
         val.wrapping_mul(2654435761) >> HASH_SLIDE
     }
+
+    fn insert_string(state: &mut State, string: usize, count: usize) {
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+        if count <= avx2::MAX_BATCH && std::is_x86_feature_detected!("avx2") {
+            // SAFETY: the `avx2` feature was just confirmed to be present.
+            unsafe { avx2::insert_string(state, string, count) };
+            return;
+        }
+
+        Self::insert_string_scalar(state, string, count)
+    }
+}
+
+impl StandardHashCalc {
+    /// The scalar path: hashes each position and runs the sequential
+    /// `prev`/`head` chain update in the same loop. The SIMD path below does
+    /// not call this; it keeps its own copy of the chain-update loop. That
+    /// update has a genuine cross-iteration dependency (each insert can
+    /// observe the previous one's `head` write) and so is never vectorized.
+    fn insert_string_scalar(state: &mut State, string: usize, count: usize) {
+        let slice = &state.window.filled()[string + Self::HASH_CALC_OFFSET..];
+
+        // .take(count) generates worse assembly
+        for (i, w) in slice[..count + 3].windows(4).enumerate() {
+            let idx = string as u16 + i as u16;
+
+            let val = u32::from_ne_bytes(w.try_into().unwrap());
+
+            let hm = (Self::hash_calc(0, val) & Self::HASH_CALC_MASK) as usize;
+
+            let head = state.head[hm];
+            if head != idx {
+                state.prev[idx as usize & state.w_mask] = head;
+                state.head[hm] = idx;
+            }
+        }
+    }
+}
+
+/// AVX2 batch hashing for [`StandardHashCalc`].
+///
+/// The multiplicative hash used by `StandardHashCalc` has no cross-iteration
+/// dependency, unlike the `prev`/`head` chain update, so it is split into two
+/// phases: precompute all `count` hash indices with 256-bit lanes, then run
+/// the existing sequential chain-update loop over the precomputed indices.
+/// This mirrors the portable/NEON batching split used in BLAKE3's guts.
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+mod avx2 {
+    use super::{HashCalc, StandardHashCalc, State};
+
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    const LANES: usize = 8;
+
+    /// Upper bound on `count` for a single batch; callers with a longer run
+    /// fall back to the scalar path rather than growing the stack buffer.
+    pub(super) const MAX_BATCH: usize = 258;
+
+    /// # Safety
+    /// Caller must ensure the `avx2` target feature is available.
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn insert_string(state: &mut State, string: usize, count: usize) {
+        let slice = &state.window.filled()[string + StandardHashCalc::HASH_CALC_OFFSET..];
+        let hashes = compute_hashes(slice, count);
+
+        // The chain update must stay strictly in order to preserve identical
+        // `head`/`prev` contents to the scalar implementation.
+        for (i, &hm) in hashes[..count].iter().enumerate() {
+            let idx = string as u16 + i as u16;
+            let hm = hm as usize;
+
+            let head = state.head[hm];
+            if head != idx {
+                state.prev[idx as usize & state.w_mask] = head;
+                state.head[hm] = idx;
+            }
+        }
+    }
+
+    /// Precomputes all `count` hash indices for `slice` using 256-bit lanes,
+    /// falling back to scalar for the remainder that doesn't fill a full
+    /// lane group. Pulled out of `insert_string` so it can be tested against
+    /// [`StandardHashCalc::hash_calc`] directly, independent of `State`.
+    ///
+    /// # Safety
+    /// Caller must ensure the `avx2` target feature is available.
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn compute_hashes(slice: &[u8], count: usize) -> [u32; MAX_BATCH] {
+        const HASH_SLIDE: i32 = 16;
+
+        let mut hashes = [0u32; MAX_BATCH];
+
+        let mult = _mm256_set1_epi32(2654435761u32 as i32);
+        let mask = _mm256_set1_epi32(StandardHashCalc::HASH_CALC_MASK as i32);
+
+        let mut i = 0;
+        while i + LANES <= count {
+            // Load the 8 overlapping unaligned 4-byte windows starting at `i`.
+            let mut vals = [0u32; LANES];
+            for (lane, v) in vals.iter_mut().enumerate() {
+                *v = u32::from_ne_bytes(slice[i + lane..i + lane + 4].try_into().unwrap());
+            }
+            let v = _mm256_loadu_si256(vals.as_ptr().cast());
+
+            let h = _mm256_mullo_epi32(v, mult);
+            let h = _mm256_srli_epi32(h, HASH_SLIDE);
+            let h = _mm256_and_si256(h, mask);
+
+            _mm256_storeu_si256(hashes[i..].as_mut_ptr().cast(), h);
+
+            i += LANES;
+        }
+
+        // Scalar tail for the remainder that doesn't fill a full lane group.
+        while i < count {
+            let val = u32::from_ne_bytes(slice[i..i + 4].try_into().unwrap());
+            hashes[i] = StandardHashCalc::hash_calc(0, val) & StandardHashCalc::HASH_CALC_MASK;
+            i += 1;
+        }
+
+        hashes
+    }
 }
 
 pub struct RollHashCalc;
@@ -117,6 +310,12 @@ pub struct Crc32HashCalc;
 
 impl Crc32HashCalc {
     pub fn is_supported() -> bool {
+        // Under Miri, `hash_calc` uses the software fallback below instead of
+        // the unsupported hardware intrinsics, so the variant is always usable.
+        if cfg!(miri) {
+            return true;
+        }
+
         if cfg!(target_arch = "x86") || cfg!(target_arch = "x86_64") {
             return true;
         }
@@ -134,32 +333,249 @@ impl HashCalc for Crc32HashCalc {
 
     const HASH_CALC_MASK: u32 = (HASH_SIZE - 1) as u32;
 
-    #[cfg(target_arch = "x86")]
+    // `_mm_crc32_u32`/`__crc32cw` don't execute under Miri, so use a
+    // table-free software CRC32C step that produces identical results,
+    // letting the whole match-finder be exercised under `cargo miri test`.
+    #[cfg(miri)]
+    fn hash_calc(h: u32, val: u32) -> u32 {
+        crc32c_word_sw(h, val)
+    }
+
+    #[cfg(all(target_arch = "x86", not(miri)))]
     fn hash_calc(h: u32, val: u32) -> u32 {
         unsafe { core::arch::x86::_mm_crc32_u32(h, val) }
     }
 
-    #[cfg(target_arch = "x86_64")]
+    #[cfg(all(target_arch = "x86_64", not(miri)))]
     fn hash_calc(h: u32, val: u32) -> u32 {
         unsafe { core::arch::x86_64::_mm_crc32_u32(h, val) }
     }
 
-    #[cfg(target_arch = "aarch64")]
+    #[cfg(all(target_arch = "aarch64", not(miri)))]
     fn hash_calc(h: u32, val: u32) -> u32 {
         unsafe { crate::crc32::acle::__crc32cw(h, val) }
     }
 
-    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+    #[cfg(not(any(
+        miri,
+        target_arch = "x86",
+        target_arch = "x86_64",
+        target_arch = "aarch64"
+    )))]
     fn hash_calc(h: u32, val: u32) -> u32 {
         assert!(!Self::is_supported());
         unimplemented!("there is no hardware support on this platform")
     }
 }
 
+pub struct AesHashCalc;
+
+impl AesHashCalc {
+    /// Detects whether AES hardware is available, caching the result after
+    /// the first call. `hash_calc` calls this once per 4-byte window, so
+    /// (mirroring `HashCalcVariant::detect()`'s cached dispatch) it must not
+    /// re-run CPU feature detection every time.
+    ///
+    /// x86/x86_64-only for now: there's no ARM crypto-extension round
+    /// implemented below (only the `aes` module's AES-NI path), so this must
+    /// not report `true` on aarch64 — doing so would make `hash_calc` and
+    /// `HashCalcVariant::detect()` silently fall back to (and prefer) the
+    /// slower multiplicative hash while claiming AES support.
+    pub fn is_supported() -> bool {
+        const UNINIT: u8 = 0;
+        const UNSUPPORTED: u8 = 1;
+        const SUPPORTED: u8 = 2;
+
+        static CACHE: AtomicU8 = AtomicU8::new(UNINIT);
+
+        match CACHE.load(Ordering::Relaxed) {
+            SUPPORTED => return true,
+            UNSUPPORTED => return false,
+            _ => {}
+        }
+
+        let supported = Self::detect_supported();
+        CACHE.store(
+            if supported { SUPPORTED } else { UNSUPPORTED },
+            Ordering::Relaxed,
+        );
+
+        supported
+    }
+
+    fn detect_supported() -> bool {
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+        return std::is_x86_feature_detected!("aes");
+
+        #[allow(unreachable_code)]
+        false
+    }
+}
+
+impl HashCalc for AesHashCalc {
+    const HASH_CALC_OFFSET: usize = 0;
+
+    const HASH_CALC_MASK: u32 = (HASH_SIZE - 1) as u32;
+
+    // A single AES round over the 4-byte window gives near-ideal avalanche
+    // (cf. ahash's `aes_hash`), spreading entries more evenly across
+    // `head`/`prev` than the multiplicative hash on structured/repetitive
+    // input, which shortens match-finder chain walks. Falls back to the
+    // multiplicative hash when AES hardware isn't available.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    fn hash_calc(h: u32, val: u32) -> u32 {
+        if Self::is_supported() {
+            unsafe { aes::round_hash(val) }
+        } else {
+            StandardHashCalc::hash_calc(h, val)
+        }
+    }
+
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    fn hash_calc(h: u32, val: u32) -> u32 {
+        StandardHashCalc::hash_calc(h, val)
+    }
+}
+
+/// AES-NI backed hashing for [`AesHashCalc`].
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod aes {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    /// Fixed round key; only avalanche, not cryptographic strength, is needed
+    /// here, so any non-trivial constant works.
+    const KEY: [u8; 16] = [
+        0x9e, 0x37, 0x79, 0xb9, 0x7f, 0x4a, 0x7c, 0x15, 0xf3, 0x9c, 0xc0, 0x60, 0x5c, 0xed, 0xc8,
+        0x35,
+    ];
+
+    /// # Safety
+    /// Caller must ensure the `aes` target feature is available.
+    #[target_feature(enable = "aes")]
+    pub(super) unsafe fn round_hash(val: u32) -> u32 {
+        let block = _mm_cvtsi32_si128(val as i32);
+        let key = _mm_loadu_si128(KEY.as_ptr().cast());
+        let mixed = _mm_aesenc_si128(block, key);
+        _mm_cvtsi128_si32(mixed) as u32
+    }
+}
+
+/// Bitwise, table-free software implementation of the CRC32C (Castagnoli)
+/// word step, used in place of `_mm_crc32_u32`/`__crc32cw` under Miri, which
+/// can't execute those hardware intrinsics.
+#[cfg(miri)]
+fn crc32c_word_sw(crc: u32, val: u32) -> u32 {
+    const POLY: u32 = 0x82f6_3b78; // reflected CRC32C polynomial (0x1EDC6F41)
+
+    let mut crc = crc ^ val;
+    for _ in 0..32 {
+        crc = if crc & 1 != 0 {
+            (crc >> 1) ^ POLY
+        } else {
+            crc >> 1
+        };
+    }
+    crc
+}
+
+/// Per-bucket chain-length statistics for the match-finder hash table
+/// (`state.head`/`state.prev`), gathered during a dry run to empirically
+/// compare [`HashCalcVariant`]s on a corpus.
+///
+/// Collection walks every chain, so it is for offline tuning, not hot loops.
+///
+/// This is diagnostics only: the hash-table width (`HashCalc::HASH_CALC_MASK`)
+/// is still fixed per variant. Making it a real tunable parameter would mean
+/// resizing `State`'s `head`/`prev` allocations to match, which this module
+/// doesn't own and doesn't do — that part of the original request is not
+/// implemented here.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ChainStats {
+    pub buckets_used: usize,
+    pub max_chain_len: usize,
+    pub avg_chain_len: f64,
+}
+
+impl ChainStats {
+    /// Walks every `head` bucket's `prev` chain and tallies its length. `0`
+    /// is treated as the empty-bucket/end-of-chain sentinel, matching zlib's
+    /// `NIL`.
+    pub fn collect(state: &State) -> Self {
+        let mut buckets_used = 0usize;
+        let mut max_chain_len = 0usize;
+        let mut total_len = 0usize;
+
+        for &head in state.head.iter() {
+            if head == 0 {
+                continue;
+            }
+
+            buckets_used += 1;
+
+            let mut len = 1usize;
+            let mut node = head;
+            while state.prev[node as usize & state.w_mask] != 0 {
+                node = state.prev[node as usize & state.w_mask];
+                len += 1;
+            }
+
+            total_len += len;
+            max_chain_len = max_chain_len.max(len);
+        }
+
+        let avg_chain_len = if buckets_used == 0 {
+            0.0
+        } else {
+            total_len as f64 / buckets_used as f64
+        };
+
+        ChainStats {
+            buckets_used,
+            max_chain_len,
+            avg_chain_len,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn detect_is_stable_across_calls() {
+        let first = HashCalcVariant::detect();
+        for _ in 0..4 {
+            assert_eq!(HashCalcVariant::detect(), first);
+        }
+    }
+
+    #[test]
+    fn update_hash_dispatch_matches_concrete_impl() {
+        const SAMPLES: [(u32, u32); 3] = [(0, 807411760), (2565, 93), (0xdeadbeef, 0x12345678)];
+
+        for (h, val) in SAMPLES {
+            assert_eq!(
+                HashCalcVariant::Standard.update_hash(h, val),
+                StandardHashCalc::update_hash(h, val)
+            );
+            assert_eq!(
+                HashCalcVariant::Crc32.update_hash(h, val),
+                Crc32HashCalc::update_hash(h, val)
+            );
+            assert_eq!(
+                HashCalcVariant::Roll.update_hash(h, val),
+                RollHashCalc::update_hash(h, val)
+            );
+            assert_eq!(
+                HashCalcVariant::Aes.update_hash(h, val),
+                AesHashCalc::update_hash(h, val)
+            );
+        }
+    }
+
     #[test]
     fn crc32_hash_calc() {
         assert_eq!(Crc32HashCalc::hash_calc(0, 807411760), 2423125009);
@@ -199,4 +615,45 @@ mod tests {
         assert_eq!(RollHashCalc::hash_calc(3826, 117), 122421);
         assert_eq!(RollHashCalc::hash_calc(24117, 101), 771781);
     }
+
+    #[test]
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+    fn avx2_hashes_match_scalar() {
+        if !std::is_x86_feature_detected!("avx2") {
+            return;
+        }
+
+        // Arbitrary, non-constant bytes so lanes don't collapse to the same
+        // hash and mask a lane-ordering bug.
+        let data: [u8; avx2::MAX_BATCH + 16] = std::array::from_fn(|i| (i as u32 * 37 + 11) as u8);
+
+        for &count in &[0usize, 1, 7, 8, 9, 257, 258] {
+            let scalar: Vec<u32> = data[..count + 3]
+                .windows(4)
+                .take(count)
+                .map(|w| {
+                    let val = u32::from_ne_bytes(w.try_into().unwrap());
+                    StandardHashCalc::hash_calc(0, val) & StandardHashCalc::HASH_CALC_MASK
+                })
+                .collect();
+
+            let simd = unsafe { avx2::compute_hashes(&data, count) };
+
+            assert_eq!(simd[..count], scalar[..], "mismatch at count = {count}");
+        }
+    }
+
+    #[test]
+    fn aes_hash_calc() {
+        if !AesHashCalc::is_supported() {
+            return;
+        }
+
+        assert_eq!(AesHashCalc::hash_calc(0, 0), 3659158781);
+        assert_eq!(AesHashCalc::hash_calc(0, 1), 4211428291);
+        assert_eq!(AesHashCalc::hash_calc(0, 0x12345678), 2697300824);
+        assert_eq!(AesHashCalc::hash_calc(0, 0xdeadbeef), 94824606);
+        assert_eq!(AesHashCalc::hash_calc(0, 0x0a0b0c0d), 498000014);
+        assert_eq!(AesHashCalc::hash_calc(0, 0xffffffff), 1164910871);
+    }
 }